@@ -1,22 +1,27 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::time::SystemTime;
 use std::{io, time::Duration};
 
 use crossterm::{
     cursor,
-    event::{self, EnableMouseCapture, Event, KeyCode, MouseButton, MouseEvent, MouseEventKind},
+    event::{
+        self, EnableMouseCapture, Event, KeyCode, KeyModifiers, MouseButton, MouseEvent,
+        MouseEventKind,
+    },
     execute, queue,
     style::{self, Stylize},
     terminal,
 };
 
 const KEYBIND_TEXT: &str =
-    "<space> play/pause • <left-click> add • <right-click> remove • s save current cells • l restore last save • c clear • r reset generation • <arrow-up/down> change speed • q quit";
+    "<space> play/pause • <left-click> add • <right-click> remove • s save current cells • L restore last save • c clear • r reset generation • u undo • Ctrl-r redo • hjkl/arrows pan • [/] brush size • m symmetry • +/- change bpm • p sequencer • M mute • y scale • </> root note • : command • q quit";
 const CELL: &str = "██";
-const UPDATE_MS: u128 = 200;
 const MSG_DISPLAY_MS: u128 = 1000;
+const COUNT_TIMEOUT_MS: u128 = 1000;
+const UNDO_DEPTH: usize = 256;
 
-type Coord = (u16, u16);
+/// A cell's position in the unbounded world grid, independent of the terminal size.
+type Coord = (i64, i64);
 
 fn main() -> io::Result<()> {
     let mut stdout = io::stdout();
@@ -28,32 +33,187 @@ enum AppAction {
     Quit,
 }
 
+/// Input mode, following the vim-style modal convention: `Normal` handles
+/// single/multi-key bindings directly, `Command` reads a `:`-prefixed line.
+enum Mode {
+    Normal,
+    Command,
+}
+
+/// Mirrors brush strokes across the center of the current viewport, so
+/// symmetric seed patterns can be hand-drawn without painting every quadrant.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Symmetry {
+    None,
+    Horizontal,
+    Vertical,
+    Quad,
+}
+
+impl Symmetry {
+    fn next(self) -> Symmetry {
+        match self {
+            Symmetry::None => Symmetry::Horizontal,
+            Symmetry::Horizontal => Symmetry::Vertical,
+            Symmetry::Vertical => Symmetry::Quad,
+            Symmetry::Quad => Symmetry::None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Symmetry::None => "none",
+            Symmetry::Horizontal => "horizontal",
+            Symmetry::Vertical => "vertical",
+            Symmetry::Quad => "quad",
+        }
+    }
+}
+
+/// A musical scale the sequencer quantizes cell rows onto, as semitone
+/// offsets from the root note within one octave.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Scale {
+    Major,
+    Minor,
+    Pentatonic,
+    Chromatic,
+}
+
+impl Scale {
+    fn degrees(self) -> &'static [i64] {
+        match self {
+            Scale::Major => &[0, 2, 4, 5, 7, 9, 11],
+            Scale::Minor => &[0, 2, 3, 5, 7, 8, 10],
+            Scale::Pentatonic => &[0, 2, 4, 7, 9],
+            Scale::Chromatic => &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+        }
+    }
+
+    fn next(self) -> Scale {
+        match self {
+            Scale::Major => Scale::Minor,
+            Scale::Minor => Scale::Pentatonic,
+            Scale::Pentatonic => Scale::Chromatic,
+            Scale::Chromatic => Scale::Major,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Scale::Major => "major",
+            Scale::Minor => "minor",
+            Scale::Pentatonic => "pentatonic",
+            Scale::Chromatic => "chromatic",
+        }
+    }
+}
+
+/// Output backend for sequencer note events. A real build would implement
+/// this over a MIDI port (e.g. via `midir`) or an audio device; `LoggingSynth`
+/// is the backend used here, since this environment has no sound hardware —
+/// it surfaces each note on stderr instead.
+trait Synth {
+    fn note_on(&mut self, pitch: u8);
+}
+
+struct LoggingSynth;
+
+impl Synth for LoggingSynth {
+    fn note_on(&mut self, pitch: u8) {
+        eprintln!("note_on: {} ({pitch})", note_name(pitch));
+    }
+}
+
+/// Renders a MIDI note number as e.g. `C#4`, using the MIDI convention where
+/// note 60 (middle C) falls in octave 4.
+fn note_name(pitch: u8) -> String {
+    const NAMES: [&str; 12] = [
+        "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+    ];
+    let octave = pitch as i32 / 12 - 1;
+    format!("{}{octave}", NAMES[pitch as usize % 12])
+}
+
 struct App {
     alive_cells: HashSet<Coord>,
-    save: HashSet<Coord>,
+    save_slots: HashMap<u32, HashSet<Coord>>,
+
+    /// Snapshots taken before each mutating action; `u` pops the most recent
+    /// one back into `alive_cells`, pushing the current state onto `redo`.
+    undo: Vec<HashSet<Coord>>,
+    redo: Vec<HashSet<Coord>>,
+
+    /// Top-left world coordinate currently visible on screen, like a camera
+    /// that pans over the (otherwise infinite) simulation.
+    cam_x: i64,
+    cam_y: i64,
+
+    /// Radius of the square brush painted by mouse strokes; 0 paints a single cell.
+    brush_size: u16,
+    symmetry: Symmetry,
 
     is_playing: bool,
-    speed: f32,
+    bpm: u16,
     last_updated: SystemTime,
     generation: usize,
     current_msg: Option<String>,
     msg_display_start: SystemTime,
+
+    /// Sweeps across world x-coordinates one step per beat; cells it crosses
+    /// are played as notes instead of just simulated.
+    sequencer_enabled: bool,
+    muted: bool,
+    playhead_x: i64,
+    current_beat: usize,
+    root: u8,
+    scale: Scale,
+    synth: LoggingSynth,
+
+    mode: Mode,
+    /// Digits typed so far for a pending numeric prefix (e.g. the `5` in `5s`).
+    count_buffer: String,
+    count_buffer_started: SystemTime,
+    /// Text typed so far in Command mode, not including the leading `:`.
+    command_buffer: String,
 }
 
 impl App {
     fn new() -> App {
         App {
             alive_cells: HashSet::new(),
-            save: HashSet::new(),
+            save_slots: HashMap::new(),
+            undo: Vec::new(),
+            redo: Vec::new(),
+            cam_x: 0,
+            cam_y: 0,
+            brush_size: 0,
+            symmetry: Symmetry::None,
             is_playing: false,
-            speed: 1.0,
+            bpm: 120,
             last_updated: SystemTime::now(),
             generation: 0,
             current_msg: None,
             msg_display_start: SystemTime::now(),
+            sequencer_enabled: false,
+            muted: false,
+            playhead_x: 0,
+            current_beat: 0,
+            root: 60,
+            scale: Scale::Major,
+            synth: LoggingSynth,
+            mode: Mode::Normal,
+            count_buffer: String::new(),
+            count_buffer_started: SystemTime::now(),
+            command_buffer: String::new(),
         }
     }
 
+    fn set_msg(&mut self, msg: impl Into<String>) {
+        self.current_msg = Some(msg.into());
+        self.msg_display_start = SystemTime::now();
+    }
+
     fn draw<W: io::Write>(&self, write: &mut W) -> io::Result<()> {
         queue!(
             write,
@@ -65,28 +225,60 @@ impl App {
 
         let size = terminal::size().unwrap();
 
-        for (col, row) in &self.alive_cells {
+        if self.sequencer_enabled {
+            if let Some((col, _)) = self.world_to_screen((self.playhead_x, self.cam_y), size) {
+                let marker = CELL.dark_cyan().stylize();
+                for row in 0..size.1 {
+                    queue!(
+                        write,
+                        cursor::MoveTo(col, row),
+                        style::PrintStyledContent(marker)
+                    )?;
+                }
+            }
+        }
+
+        for &(x, y) in &self.alive_cells {
+            let Some((col, row)) = self.world_to_screen((x, y), size) else {
+                continue;
+            };
             let text = CELL.yellow().stylize();
             queue!(
                 write,
-                cursor::MoveTo(*col, *row),
+                cursor::MoveTo(col, row),
                 style::PrintStyledContent(text)
             )?;
         }
 
-        let raw_info_text = if let Some(msg) = &self.current_msg {
+        let raw_info_text = if let Mode::Command = self.mode {
+            format!(":{}", self.command_buffer)
+        } else if let Some(msg) = &self.current_msg {
             msg.to_string()
         } else {
             format!(
-                "{} ({:.1}x) - Generation: {}, Cells: {}",
+                "{} ({} bpm) - Generation: {}, Cells: {}, Camera: ({}, {}), Brush: {}, Symmetry: {}{}",
                 if !self.is_playing {
                     "Paused"
                 } else {
                     "Playing"
                 },
-                self.speed,
+                self.bpm,
                 self.generation,
                 self.alive_cells.len(),
+                self.cam_x,
+                self.cam_y,
+                self.brush_size + 1,
+                self.symmetry.label(),
+                if self.sequencer_enabled {
+                    format!(
+                        ", Beat: {} ({}{})",
+                        self.current_beat,
+                        self.scale.label(),
+                        if self.muted { ", muted" } else { "" },
+                    )
+                } else {
+                    String::new()
+                },
             )
         };
         let raw_info_text_len = raw_info_text.len();
@@ -124,46 +316,170 @@ impl App {
         if event::poll(Duration::ZERO)? {
             match event::read()? {
                 Event::Key(key) => 'blk: {
-                    match key.code {
-                        KeyCode::Char('q') => return Ok(AppAction::Quit),
-                        KeyCode::Char(' ') => self.is_playing = !self.is_playing,
-                        KeyCode::Char('r') => {
-                            self.is_playing = false;
-                            self.generation = 0;
-                        }
-                        KeyCode::Char('c') => {
-                            self.is_playing = false;
-                            self.alive_cells.clear();
-                            self.generation = 0;
-                        }
-                        KeyCode::Char('s') => {
-                            self.save = self.alive_cells.clone();
-                            self.current_msg = Some("Current cells saved!".to_string());
-                            self.msg_display_start = SystemTime::now();
+                    match self.mode {
+                        Mode::Command => match key.code {
+                            KeyCode::Enter => {
+                                self.mode = Mode::Normal;
+                                let cmd = std::mem::take(&mut self.command_buffer);
+                                if let AppAction::Quit = self.run_command(&cmd) {
+                                    return Ok(AppAction::Quit);
+                                }
+                            }
+                            KeyCode::Esc => {
+                                self.mode = Mode::Normal;
+                                self.command_buffer.clear();
+                            }
+                            KeyCode::Backspace => {
+                                self.command_buffer.pop();
+                            }
+                            KeyCode::Char(c) => self.command_buffer.push(c),
+                            _ => break 'blk,
+                        },
+                        Mode::Normal => {
+                            if let KeyCode::Char(':') = key.code {
+                                self.mode = Mode::Command;
+                                self.command_buffer.clear();
+                                self.count_buffer.clear();
+                                need_redraw = true;
+                                break 'blk;
+                            }
+
+                            if let KeyCode::Char(c) = key.code {
+                                if c.is_ascii_digit() && !(c == '0' && self.count_buffer.is_empty())
+                                {
+                                    if !self.count_buffer.is_empty()
+                                        && self.count_buffer_elapsed() > COUNT_TIMEOUT_MS
+                                    {
+                                        self.count_buffer.clear();
+                                    }
+                                    if self.count_buffer.is_empty() {
+                                        self.count_buffer_started = SystemTime::now();
+                                    }
+                                    self.count_buffer.push(c);
+                                    need_redraw = true;
+                                    break 'blk;
+                                }
+                            }
+
+                            let count = if !self.count_buffer.is_empty()
+                                && self.count_buffer_elapsed() <= COUNT_TIMEOUT_MS
+                            {
+                                self.count_buffer.parse().unwrap_or(1)
+                            } else {
+                                1u32
+                            };
+                            let had_count = !self.count_buffer.is_empty();
+                            self.count_buffer.clear();
+
+                            match key.code {
+                                KeyCode::Char('q') => return Ok(AppAction::Quit),
+                                KeyCode::Char(' ') => {
+                                    if had_count {
+                                        self.snapshot();
+                                        for _ in 0..count {
+                                            self.next_generation();
+                                            self.generation += 1;
+                                        }
+                                    } else {
+                                        self.is_playing = !self.is_playing;
+                                    }
+                                }
+                                KeyCode::Char('r')
+                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    self.redo()
+                                }
+                                KeyCode::Char('r') => {
+                                    self.is_playing = false;
+                                    self.generation = 0;
+                                }
+                                KeyCode::Char('u') => self.undo(),
+                                KeyCode::Char('c') => {
+                                    self.snapshot();
+                                    self.is_playing = false;
+                                    self.alive_cells.clear();
+                                    self.generation = 0;
+                                }
+                                KeyCode::Char('s') => {
+                                    let slot = if had_count { count } else { 0 };
+                                    self.save_slots.insert(slot, self.alive_cells.clone());
+                                    self.set_msg(format!("Saved to slot {slot}!"));
+                                }
+                                KeyCode::Char('L') => {
+                                    let slot = if had_count { count } else { 0 };
+                                    match self.save_slots.get(&slot).cloned() {
+                                        Some(save) => {
+                                            self.snapshot();
+                                            self.alive_cells = save;
+                                            self.set_msg(format!("Loaded slot {slot}!"));
+                                        }
+                                        None => self.set_msg(format!("Slot {slot} is empty!")),
+                                    }
+                                }
+                                KeyCode::Char('+') => self.bpm = self.bpm.saturating_add(5),
+                                KeyCode::Char('-') if self.bpm > 5 => self.bpm -= 5,
+                                KeyCode::Char(']') if self.brush_size < self.max_brush_size() => {
+                                    self.brush_size += 1
+                                }
+                                KeyCode::Char('[') if self.brush_size > 0 => self.brush_size -= 1,
+                                KeyCode::Char('m') => {
+                                    self.symmetry = self.symmetry.next();
+                                    self.set_msg(format!("Symmetry: {}", self.symmetry.label()));
+                                }
+                                KeyCode::Char('p') => {
+                                    self.sequencer_enabled = !self.sequencer_enabled;
+                                    self.current_beat = 0;
+                                    self.playhead_x = self.cam_x;
+                                    self.set_msg(if self.sequencer_enabled {
+                                        "Sequencer on"
+                                    } else {
+                                        "Sequencer off"
+                                    });
+                                }
+                                KeyCode::Char('M') => {
+                                    self.muted = !self.muted;
+                                    self.set_msg(if self.muted { "Muted" } else { "Unmuted" });
+                                }
+                                KeyCode::Char('y') => {
+                                    self.scale = self.scale.next();
+                                    self.set_msg(format!("Scale: {}", self.scale.label()));
+                                }
+                                KeyCode::Char('<') if self.root > 0 => {
+                                    self.root -= 1;
+                                    self.set_msg(format!("Root: {}", note_name(self.root)));
+                                }
+                                KeyCode::Char('>') if self.root < 127 => {
+                                    self.root += 1;
+                                    self.set_msg(format!("Root: {}", note_name(self.root)));
+                                }
+                                KeyCode::Char('h') | KeyCode::Left => self.pan(-(count as i64), 0),
+                                KeyCode::Char('l') | KeyCode::Right => self.pan(count as i64, 0),
+                                KeyCode::Char('k') | KeyCode::Up => self.pan(0, -(count as i64)),
+                                KeyCode::Char('j') | KeyCode::Down => self.pan(0, count as i64),
+                                _ => break 'blk,
+                            }
                         }
-                        KeyCode::Char('l') => {
-                            self.alive_cells = self.save.clone();
-                            self.current_msg = Some("Last save loaded!".to_string());
-                            self.msg_display_start = SystemTime::now();
-                        }
-                        KeyCode::Up => self.speed += 0.5,
-                        KeyCode::Down if self.speed > 0.5 => self.speed -= 0.5,
-                        _ => break 'blk,
                     }
                     need_redraw = true;
                 }
                 Event::Mouse(MouseEvent {
                     kind, column, row, ..
-                }) if !self.is_playing => 'blk: {
-                    let coord = to_point_coord(row, column);
+                }) if !self.is_playing && matches!(self.mode, Mode::Normal) => 'blk: {
+                    let coord = self.screen_to_world(row, column);
                     match kind {
-                        MouseEventKind::Drag(MouseButton::Left)
-                        | MouseEventKind::Down(MouseButton::Left) => {
-                            self.alive_cells.insert(coord);
+                        MouseEventKind::Down(MouseButton::Left) => {
+                            self.snapshot();
+                            self.paint(coord, true);
+                        }
+                        MouseEventKind::Drag(MouseButton::Left) => {
+                            self.paint(coord, true);
                         }
-                        MouseEventKind::Drag(MouseButton::Right)
-                        | MouseEventKind::Down(MouseButton::Right) => {
-                            self.alive_cells.retain(|c| *c != coord);
+                        MouseEventKind::Down(MouseButton::Right) => {
+                            self.snapshot();
+                            self.paint(coord, false);
+                        }
+                        MouseEventKind::Drag(MouseButton::Right) => {
+                            self.paint(coord, false);
                         }
                         _ => break 'blk,
                     }
@@ -177,17 +493,317 @@ impl App {
         Ok(AppAction::NeedRedraw(need_redraw))
     }
 
+    fn count_buffer_elapsed(&self) -> u128 {
+        self.count_buffer_started.elapsed().unwrap().as_millis()
+    }
+
+    fn pan(&mut self, dx: i64, dy: i64) {
+        self.cam_x += dx;
+        self.cam_y += dy;
+    }
+
+    fn beat_period_ms(&self) -> u128 {
+        60_000 / self.bpm.max(1) as u128
+    }
+
+    /// Advances the playhead by one column and plays every live cell it just
+    /// swept over, quantizing each cell's row onto the current scale.
+    fn play_beat(&mut self) {
+        self.current_beat += 1;
+        let x = self.playhead_x;
+        self.playhead_x += 1;
+
+        if self.muted {
+            return;
+        }
+
+        let mut rows: Vec<i64> = self
+            .alive_cells
+            .iter()
+            .filter(|&&(cx, _)| cx == x)
+            .map(|&(_, y)| y)
+            .collect();
+        rows.sort_unstable();
+
+        for row in rows {
+            let pitch = self.pitch_for_row(row);
+            self.synth.note_on(pitch);
+        }
+    }
+
+    /// Quantizes a cell's world row onto the active scale, wrapping into
+    /// higher/lower octaves the further the row is from the root.
+    fn pitch_for_row(&self, row: i64) -> u8 {
+        let degrees = self.scale.degrees();
+        let len = degrees.len() as i64;
+        let degree = row.rem_euclid(len) as usize;
+        let octave = row.div_euclid(len);
+        let semitone = degrees[degree] + octave * 12;
+        (self.root as i64 + semitone).clamp(0, 127) as u8
+    }
+
+    /// Records the current `alive_cells` on the undo stack; call this before
+    /// a mutation, not after, so `undo` restores the pre-mutation state.
+    fn snapshot(&mut self) {
+        self.undo.push(self.alive_cells.clone());
+        if self.undo.len() > UNDO_DEPTH {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// Serializes `alive_cells` to the standard Life RLE format, normalized
+    /// so the pattern's bounding box starts at the origin.
+    fn to_rle(&self) -> String {
+        let Some((min, max)) = bounds(&self.alive_cells) else {
+            return "x = 0, y = 0, rule = B3/S23\n!\n".to_string();
+        };
+        let width = max.0 - min.0 + 1;
+        let height = max.1 - min.1 + 1;
+
+        let mut rows = Vec::new();
+        for y in min.1..=max.1 {
+            let mut row = String::new();
+            let mut run_char = 'b';
+            let mut run_len = 0u32;
+            for x in min.0..=max.0 {
+                let c = if self.alive_cells.contains(&(x, y)) {
+                    'o'
+                } else {
+                    'b'
+                };
+                if run_len > 0 && c == run_char {
+                    run_len += 1;
+                } else {
+                    // Mid-row runs of dead cells still need to be emitted to
+                    // preserve alignment; only a row's *trailing* dead run is
+                    // dropped, per the RLE convention of omitting it below.
+                    if run_len > 0 {
+                        push_run(&mut row, run_len, run_char);
+                    }
+                    run_char = c;
+                    run_len = 1;
+                }
+            }
+            if run_len > 0 && run_char == 'o' {
+                push_run(&mut row, run_len, run_char);
+            }
+            rows.push(row);
+        }
+
+        format!(
+            "x = {width}, y = {height}, rule = B3/S23\n{}\n!\n",
+            rows.join("$\n")
+        )
+    }
+
+    /// Parses `rle`, translates the pattern's local coordinates onto the
+    /// current camera center, and loads it as the new `alive_cells`.
+    fn load_rle(&mut self, rle: &str, label: &str) {
+        match parse_rle(rle) {
+            Ok(cells) => {
+                self.snapshot();
+                self.alive_cells = self.center_on_camera(cells);
+                self.set_msg(format!("Loaded {label}"));
+            }
+            Err(e) => self.set_msg(format!("Failed to parse {label}: {e}")),
+        }
+    }
+
+    /// Shifts pattern-local coordinates so the pattern's center lands on the
+    /// center of the currently visible viewport.
+    fn center_on_camera(&self, cells: HashSet<Coord>) -> HashSet<Coord> {
+        let Some((min, max)) = bounds(&cells) else {
+            return cells;
+        };
+
+        let size = terminal::size().unwrap();
+        let viewport_center_x = self.cam_x + (size.0 / 2 / 2) as i64;
+        let viewport_center_y = self.cam_y + (size.1 / 2) as i64;
+        let dx = viewport_center_x - (min.0 + max.0) / 2;
+        let dy = viewport_center_y - (min.1 + max.1) / 2;
+
+        cells.into_iter().map(|(x, y)| (x + dx, y + dy)).collect()
+    }
+
+    fn undo(&mut self) {
+        match self.undo.pop() {
+            Some(prev) => {
+                let current = std::mem::replace(&mut self.alive_cells, prev);
+                self.redo.push(current);
+                if self.redo.len() > UNDO_DEPTH {
+                    self.redo.remove(0);
+                }
+            }
+            None => self.set_msg("Nothing to undo"),
+        }
+    }
+
+    fn redo(&mut self) {
+        match self.redo.pop() {
+            Some(next) => {
+                let current = std::mem::replace(&mut self.alive_cells, next);
+                self.undo.push(current);
+                if self.undo.len() > UNDO_DEPTH {
+                    self.undo.remove(0);
+                }
+            }
+            None => self.set_msg("Nothing to redo"),
+        }
+    }
+
+    /// Converts a world coordinate to on-screen `(col, row)`, or `None` if the
+    /// camera currently has it scrolled out of view.
+    fn world_to_screen(&self, (x, y): Coord, size: (u16, u16)) -> Option<(u16, u16)> {
+        let col = (x - self.cam_x) * 2;
+        let row = y - self.cam_y;
+        if col < 0 || row < 0 || col >= size.0 as i64 || row >= size.1 as i64 {
+            return None;
+        }
+        Some((col as u16, row as u16))
+    }
+
+    /// Converts a mouse click's screen position to the world coordinate under
+    /// the camera, snapping to the even column each double-wide cell occupies.
+    fn screen_to_world(&self, row: u16, column: u16) -> Coord {
+        let col = if column.is_multiple_of(2) {
+            column
+        } else {
+            column - 1
+        };
+        (self.cam_x + (col / 2) as i64, self.cam_y + row as i64)
+    }
+
+    /// Largest brush radius that still fits within the current viewport, so a
+    /// held `]` can't grow `paint()`'s `(2r+1)^2` sweep into a UI-hanging size.
+    fn max_brush_size(&self) -> u16 {
+        let size = terminal::size().unwrap();
+        (size.0 / 2).min(size.1).saturating_sub(1) / 2
+    }
+
+    /// Sets or clears every cell in a square brush centered on `center` (plus
+    /// its symmetric reflections), rather than just the single cell under the cursor.
+    fn paint(&mut self, center: Coord, alive: bool) {
+        let size = terminal::size().unwrap();
+        let r = self.brush_size as i64;
+        for dx in -r..=r {
+            for dy in -r..=r {
+                for cell in self.mirrored((center.0 + dx, center.1 + dy), size) {
+                    if alive {
+                        self.alive_cells.insert(cell);
+                    } else {
+                        self.alive_cells.remove(&cell);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns `coord` plus its reflections across the current viewport's
+    /// center, according to the active symmetry mode. `size` is the terminal
+    /// size, passed in rather than queried per cell by `paint`'s brush sweep.
+    fn mirrored(&self, coord: Coord, size: (u16, u16)) -> Vec<Coord> {
+        let (x, y) = coord;
+        let center_x = self.cam_x + (size.0 / 2 / 2) as i64;
+        let center_y = self.cam_y + (size.1 / 2) as i64;
+        let mirror_x = 2 * center_x - x;
+        let mirror_y = 2 * center_y - y;
+
+        match self.symmetry {
+            Symmetry::None => vec![coord],
+            Symmetry::Horizontal => vec![coord, (mirror_x, y)],
+            Symmetry::Vertical => vec![coord, (x, mirror_y)],
+            Symmetry::Quad => vec![coord, (mirror_x, y), (x, mirror_y), (mirror_x, mirror_y)],
+        }
+    }
+
+    /// Parses and executes a `:`-command line, returning `AppAction::Quit` for `:quit`.
+    fn run_command(&mut self, line: &str) -> AppAction {
+        let mut parts = line.split_whitespace();
+        let Some(verb) = parts.next() else {
+            return AppAction::NeedRedraw(true);
+        };
+        let args: Vec<&str> = parts.collect();
+
+        match verb {
+            "quit" | "q" => return AppAction::Quit,
+            "step" => match args.first().and_then(|s| s.parse::<usize>().ok()) {
+                Some(n) => {
+                    self.snapshot();
+                    for _ in 0..n {
+                        self.next_generation();
+                        self.generation += 1;
+                    }
+                    self.set_msg(format!("Stepped {n} generations"));
+                }
+                None => self.set_msg("Usage: :step <n>"),
+            },
+            "bpm" => match args.first().and_then(|s| s.parse::<u16>().ok()) {
+                Some(n) if n > 0 => {
+                    self.bpm = n;
+                    self.set_msg(format!("BPM set to {n}"));
+                }
+                _ => self.set_msg("Usage: :bpm <n>"),
+            },
+            "save" => match args.first() {
+                Some(path) => match std::fs::write(path, self.to_rle()) {
+                    Ok(()) => self.set_msg(format!("Saved to {path}")),
+                    Err(e) => self.set_msg(format!("Failed to save {path}: {e}")),
+                },
+                None => self.set_msg("Usage: :save <path>"),
+            },
+            "load" => match args.first() {
+                Some(path) => match std::fs::read_to_string(path) {
+                    Ok(rle) => self.load_rle(&rle, path),
+                    Err(e) => self.set_msg(format!("Failed to load {path}: {e}")),
+                },
+                None => self.set_msg("Usage: :load <path>"),
+            },
+            "pattern" => match args.first() {
+                Some(name) => match named_pattern(name) {
+                    Some(rle) => self.load_rle(rle, name),
+                    None => self.set_msg(format!("Unknown pattern: {name}")),
+                },
+                None => self.set_msg("Usage: :pattern <glider|blinker|gosperglidergun>"),
+            },
+            "goto" => {
+                match (
+                    args.first().and_then(|s| s.parse::<i64>().ok()),
+                    args.get(1).and_then(|s| s.parse::<i64>().ok()),
+                ) {
+                    (Some(x), Some(y)) => {
+                        let size = terminal::size().unwrap();
+                        self.cam_x = x - (size.0 / 2 / 2) as i64;
+                        self.cam_y = y - (size.1 / 2) as i64;
+                        self.set_msg(format!("Centered on ({x}, {y})"));
+                    }
+                    _ => self.set_msg("Usage: :goto <x> <y>"),
+                }
+            }
+            _ => self.set_msg(format!("Unknown command: {verb}")),
+        }
+
+        AppAction::NeedRedraw(true)
+    }
+
     fn update(&mut self) -> bool {
         let mut need_redraw = false;
-        if self.is_playing {
-            if self.last_updated.elapsed().unwrap().as_millis()
-                > (UPDATE_MS as f32 / self.speed).round() as u128
-            {
-                self.next_generation();
-                need_redraw = true;
-                self.generation += 1;
-                self.last_updated = SystemTime::now();
+        if self.is_playing
+            && self.last_updated.elapsed().unwrap().as_millis() > self.beat_period_ms()
+        {
+            self.snapshot();
+            self.next_generation();
+            self.generation += 1;
+            if self.sequencer_enabled {
+                self.play_beat();
             }
+            need_redraw = true;
+            self.last_updated = SystemTime::now();
+        }
+
+        if !self.count_buffer.is_empty() && self.count_buffer_elapsed() > COUNT_TIMEOUT_MS {
+            self.count_buffer.clear();
+            need_redraw = true;
         }
 
         if self.msg_display_start.elapsed().unwrap().as_millis() > MSG_DISPLAY_MS
@@ -200,51 +816,116 @@ impl App {
         need_redraw
     }
 
+    /// Sparse Life step over the live set: counts neighbors of every live cell
+    /// (and thus every cell that could possibly change) instead of scanning
+    /// the screen rectangle, so patterns survive panning off-screen and keep
+    /// simulating outside the visible viewport.
     fn next_generation(&mut self) {
-        let size = terminal::size().unwrap();
-
-        let mut removed_points = vec![];
-        let mut new_points = vec![];
-        for column in (0..size.0).step_by(2) {
-            for row in 0..size.1 {
-                let coord = (column, row);
-
-                let mut neighbor_count = 0;
-                for col in (column.saturating_sub(2)..=(column + 2).min(size.0)).step_by(2) {
-                    for row in row.saturating_sub(1)..=(row + 1).min(size.1) {
-                        if (col, row) == coord {
-                            continue;
-                        }
-
-                        if self.alive_cells.contains(&(col, row)) {
-                            neighbor_count += 1;
-                        }
+        let mut neighbor_counts: HashMap<Coord, u8> = HashMap::new();
+        for &(x, y) in &self.alive_cells {
+            for dx in -1..=1i64 {
+                for dy in -1..=1i64 {
+                    if dx == 0 && dy == 0 {
+                        continue;
                     }
-                }
-
-                if self.alive_cells.contains(&coord) && (neighbor_count < 2 || neighbor_count > 3) {
-                    removed_points.push(coord);
-                } else if neighbor_count == 3 {
-                    new_points.push(coord);
+                    *neighbor_counts.entry((x + dx, y + dy)).or_insert(0) += 1;
                 }
             }
         }
 
-        for coord in removed_points {
-            self.alive_cells.retain(|c| *c != coord);
+        self.alive_cells = neighbor_counts
+            .into_iter()
+            .filter(|&(coord, count)| {
+                matches!(
+                    (self.alive_cells.contains(&coord), count),
+                    (true, 2) | (true, 3) | (false, 3)
+                )
+            })
+            .map(|(coord, _)| coord)
+            .collect();
+    }
+}
+
+fn bounds(cells: &HashSet<Coord>) -> Option<(Coord, Coord)> {
+    let mut it = cells.iter();
+    let &first = it.next()?;
+    let (mut min, mut max) = (first, first);
+    for &(x, y) in it {
+        min = (min.0.min(x), min.1.min(y));
+        max = (max.0.max(x), max.1.max(y));
+    }
+    Some((min, max))
+}
+
+fn push_run(buf: &mut String, len: u32, c: char) {
+    if len > 1 {
+        buf.push_str(&len.to_string());
+    }
+    buf.push(c);
+}
+
+/// Parses the standard Life RLE format into pattern-local coordinates
+/// (origin at the pattern's top-left corner). Handles run-length prefixes
+/// (`24bo`), multi-`$` row skips, and ignores `#`-prefixed comment lines.
+fn parse_rle(content: &str) -> Result<HashSet<Coord>, String> {
+    let mut cells = HashSet::new();
+    let mut x = 0i64;
+    let mut y = 0i64;
+    let mut num = String::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with('x') {
+            continue;
         }
 
-        for coord in new_points {
-            self.alive_cells.insert(coord);
+        for ch in line.chars() {
+            match ch {
+                '0'..='9' => num.push(ch),
+                'b' => {
+                    x += num_or(&mut num, 1);
+                }
+                'o' => {
+                    let n = num_or(&mut num, 1);
+                    for i in 0..n {
+                        cells.insert((x + i, y));
+                    }
+                    x += n;
+                }
+                '$' => {
+                    y += num_or(&mut num, 1);
+                    x = 0;
+                }
+                '!' => return Ok(cells),
+                _ => return Err(format!("unexpected character '{ch}' in RLE body")),
+            }
         }
     }
+
+    Ok(cells)
 }
 
-fn to_point_coord(row: u16, column: u16) -> Coord {
-    if column % 2 != 0 {
-        (column - 1, row)
+fn num_or(buf: &mut String, default: i64) -> i64 {
+    let n = if buf.is_empty() {
+        default
     } else {
-        (column, row)
+        buf.parse().unwrap_or(default)
+    };
+    buf.clear();
+    n
+}
+
+/// Bundled named patterns selectable via `:pattern <name>`.
+fn named_pattern(name: &str) -> Option<&'static str> {
+    match name {
+        "glider" => Some("x = 3, y = 3, rule = B3/S23\nbob$2bo$3o!\n"),
+        "blinker" => Some("x = 3, y = 1, rule = B3/S23\n3o!\n"),
+        "gosperglidergun" | "gosper" => Some(
+            "x = 36, y = 9, rule = B3/S23\n\
+             24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4bobo$\
+             10bo5bo7bo$11bo3bo$12b2o!\n",
+        ),
+        _ => None,
     }
 }
 